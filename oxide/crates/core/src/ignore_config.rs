@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Name of the project-level override file, discovered at the content root.
+const CONFIG_FILE_NAME: &str = ".twignore";
+
+/// A project-level override of the built-in ignore fixtures (`binary-extensions.txt`,
+/// `ignored-extensions.txt`, `ignored-files.txt`). Layered over the defaults and populated
+/// from a `.twignore` file, one directive per line:
+///
+/// - `%unset <ext-or-name>` removes a built-in entry a project wants to opt back into
+///   scanning (e.g. a project that actually wants its `.txt` files scanned). A path with no
+///   extension stays ignored even when its name is unset, so `%unset Gemfile` does not bring
+///   a `Gemfile` back into scanning.
+/// - any other non-empty, non-comment line adds a new ignored extension or filename.
+///
+/// Directives are resolved top-to-bottom, so a later line always wins over an earlier one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoreConfig {
+    ignored_extensions: BTreeSet<String>,
+    ignored_files: BTreeSet<String>,
+}
+
+impl Default for IgnoreConfig {
+    fn default() -> Self {
+        let binary_extensions = include_str!("fixtures/binary-extensions.txt").trim().lines();
+        let ignored_extensions = include_str!("fixtures/ignored-extensions.txt").trim().lines();
+        let ignored_files = include_str!("fixtures/ignored-files.txt").trim().lines();
+
+        Self {
+            ignored_extensions: binary_extensions
+                .chain(ignored_extensions)
+                .map(String::from)
+                .collect(),
+            ignored_files: ignored_files.map(String::from).collect(),
+        }
+    }
+}
+
+impl IgnoreConfig {
+    /// Discover a `.twignore` file inside `base` and layer it over the defaults. Falls back
+    /// to the defaults unchanged when no config file is present.
+    pub fn discover(base: &Path) -> Self {
+        match std::fs::read_to_string(base.join(CONFIG_FILE_NAME)) {
+            Ok(contents) => Self::parse(&contents),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Parse the contents of a `.twignore` file, layered over the defaults.
+    pub fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.strip_prefix("%unset ") {
+                Some(entry) => {
+                    let entry = entry.trim();
+                    config.ignored_extensions.remove(entry);
+                    config.ignored_files.remove(entry);
+                }
+                None => {
+                    // We don't know up-front whether the project means an extension or a
+                    // bare filename, so the entry is tracked under both — matching only
+                    // ever checks the bucket that applies to a given path anyway.
+                    config.ignored_extensions.insert(line.to_string());
+                    config.ignored_files.insert(line.to_string());
+                }
+            }
+        }
+
+        config
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(|s| self.ignored_files.contains(s))
+            .unwrap_or(false)
+        {
+            return true;
+        }
+
+        // A path with no extension at all (`Dockerfile`, `Makefile`, `LICENSE`, ...) is
+        // ignored, same as the original `is_allowed_content_path` — only a path with an
+        // extension that is not on the ignore lists is scanned.
+        path.extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| self.ignored_extensions.contains(ext))
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensionless_paths_are_ignored_by_default() {
+        let config = IgnoreConfig::default();
+        assert!(config.is_ignored(Path::new("Dockerfile")));
+        assert!(config.is_ignored(Path::new("Makefile")));
+    }
+
+    #[test]
+    fn known_extensions_are_not_ignored() {
+        let config = IgnoreConfig::default();
+        assert!(!config.is_ignored(Path::new("index.html")));
+    }
+
+    #[test]
+    fn blank_lines_and_comments_are_skipped() {
+        let config = IgnoreConfig::parse("\n  \n# a comment\n");
+        assert_eq!(config, IgnoreConfig::default());
+    }
+
+    #[test]
+    fn plain_lines_add_new_ignored_extensions_and_filenames() {
+        let config = IgnoreConfig::parse("log\nGemfile.lock\n");
+        assert!(config.is_ignored(Path::new("debug.log")));
+        assert!(config.is_ignored(Path::new("Gemfile.lock")));
+    }
+
+    #[test]
+    fn unset_opts_a_default_entry_back_into_scanning() {
+        let defaults = IgnoreConfig::default();
+        assert!(defaults.is_ignored(Path::new("notes.txt")));
+
+        let config = IgnoreConfig::parse("%unset txt\n");
+        assert!(!config.is_ignored(Path::new("notes.txt")));
+    }
+
+    #[test]
+    fn later_directives_win_over_earlier_ones() {
+        let config = IgnoreConfig::parse("%unset txt\ntxt\n");
+        assert!(config.is_ignored(Path::new("notes.txt")));
+
+        let config = IgnoreConfig::parse("txt\n%unset txt\n");
+        assert!(!config.is_ignored(Path::new("notes.txt")));
+    }
+}
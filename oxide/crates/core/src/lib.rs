@@ -1,19 +1,28 @@
 use crate::parser::Extractor;
-use ignore::WalkBuilder;
+// Candidates dedup through this instead of `String`: most are short enough (`px-4`,
+// `hover:bg-red-500`) to store inline, with no heap allocation, and only the final
+// deduplicated set pays for an owned `String` at the public API boundary.
+use compact_str::CompactString;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::{WalkBuilder, WalkState};
 use rayon::prelude::*;
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tracing::event;
 
 pub mod candidate;
 pub mod glob;
+pub mod ignore_config;
 pub mod location;
 pub mod modifier;
 pub mod parser;
 pub mod utility;
 pub mod variant;
 
+use crate::ignore_config::IgnoreConfig;
+
 #[derive(Debug, Clone)]
 pub struct ChangedContent {
     pub file: Option<PathBuf>,
@@ -31,30 +40,194 @@ pub fn parse_candidate_strings_from_files(changed_content: Vec<ChangedContent>)
             .init();
     }
 
-    parse_all_blobs(read_all_files(changed_content))
+    parse_all_blobs(read_all_files(expand_directories(changed_content)))
+}
+
+/// Expand any `ChangedContent` whose `file` points at a directory into one entry per
+/// matching file inside it, using the same allowed-path filtering as `resolve_content_paths`.
+/// This lets callers pass a mix of files, directories and inline `content` blobs in one
+/// `Vec<ChangedContent>` — a `--content ./src` style argument just scans the whole folder.
+fn expand_directories(changed_content: Vec<ChangedContent>) -> Vec<ChangedContent> {
+    changed_content
+        .into_iter()
+        .flat_map(|c| match &c.file {
+            Some(file) if file.is_dir() => {
+                // Reuse the same walker/filtering as `resolve_content_paths`/`scan` so a
+                // `.twignore` inside this directory is honored here too, instead of drifting
+                // back to the bare `is_allowed_content_path`. A `ChangedContent` carries no
+                // `sources`, so no include or exclude patterns apply to this walk.
+                let root = file.to_string_lossy().into_owned();
+                let (mut builder, compiled) = build_content_walker(&root, &[]);
+
+                builder
+                    .hidden(false)
+                    .filter_entry(move |entry| is_allowed_walk_entry(entry, &compiled))
+                    .build()
+                    .filter_map(Result::ok)
+                    .filter(|e| e.path().is_file())
+                    .map(|e| {
+                        let extension = e
+                            .path()
+                            .extension()
+                            .map(|s| s.to_str().unwrap_or_default().to_string())
+                            .unwrap_or_default();
+
+                        ChangedContent {
+                            file: Some(e.into_path()),
+                            content: None,
+                            extension,
+                        }
+                    })
+                    .collect()
+            }
+            _ => vec![c],
+        })
+        .collect()
 }
 
+/// A single content source declared by the user: an explicit `base` directory to scan,
+/// paired with a glob `pattern` that is either an include (scan files matching it) or,
+/// when `negated` is set, an exclude that prunes matching files and directories as soon
+/// as the walk reaches them.
 #[derive(Debug, Clone)]
+pub struct Source {
+    pub base: String,
+    pub pattern: String,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ContentPathInfo {
     pub base: String,
+    pub sources: Vec<Source>,
+}
+
+/// Globs compiled once up-front and shared (via `Arc`) across every thread of the
+/// parallel walk, so pattern compilation never repeats per-entry or per-thread.
+struct CompiledSources {
+    // Both includes and excludes are scoped to their own source's base directory, so
+    // matching only has to be attempted for entries that are actually inside a subtree
+    // that could match, and an exclude declared for one source never prunes paths under
+    // an unrelated one.
+    includes: Vec<(PathBuf, GlobSet)>,
+    excludes: Vec<(PathBuf, GlobSet)>,
+    ignore_config: IgnoreConfig,
+}
+
+fn build_scoped_globsets(by_base: BTreeMap<PathBuf, GlobSetBuilder>) -> Vec<(PathBuf, GlobSet)> {
+    by_base
+        .into_iter()
+        .filter_map(|(base, builder)| Some((base, builder.build().ok()?)))
+        .collect()
+}
+
+fn matches_scoped(path: &Path, scoped: &[(PathBuf, GlobSet)]) -> bool {
+    scoped
+        .iter()
+        .any(|(base, set)| path.starts_with(base) && set.is_match(path))
+}
+
+// `WalkBuilder::new(root)` never canonicalizes `root`; every `DirEntry` path it yields is
+// prefixed with `root` verbatim (e.g. root "." walking produces "./node_modules/x.js"). A
+// `Source.base` is relative to that same `root`, not to the process' current directory, so
+// it has to be joined against it the same way before it can be used as a `starts_with` prefix.
+fn resolve_source_base(root: &str, base: &str) -> PathBuf {
+    let base = Path::new(base);
+    if base.is_absolute() {
+        base.to_path_buf()
+    } else {
+        Path::new(root).join(base)
+    }
+}
+
+fn compile_sources(root: &str, sources: &[Source]) -> CompiledSources {
+    let mut includes_by_base: BTreeMap<PathBuf, GlobSetBuilder> = Default::default();
+    let mut excludes_by_base: BTreeMap<PathBuf, GlobSetBuilder> = Default::default();
+
+    for source in sources {
+        let Ok(glob) = Glob::new(&source.pattern) else {
+            continue;
+        };
+
+        let by_base = if source.negated {
+            &mut excludes_by_base
+        } else {
+            &mut includes_by_base
+        };
+
+        by_base
+            .entry(resolve_source_base(root, &source.base))
+            .or_insert_with(GlobSetBuilder::new)
+            .add(glob);
+    }
+
+    CompiledSources {
+        includes: build_scoped_globsets(includes_by_base),
+        excludes: build_scoped_globsets(excludes_by_base),
+        ignore_config: IgnoreConfig::discover(Path::new(root)),
+    }
+}
+
+/// Build the `WalkBuilder` confined to the declared include bases (falling back to `root`
+/// when none were given), together with the compiled matcher its `filter_entry` closures
+/// need. Shared by `resolve_content_paths` and `scan` so the two entry points never drift
+/// apart on what counts as an allowed content path.
+fn build_content_walker(root: &str, sources: &[Source]) -> (WalkBuilder, Arc<CompiledSources>) {
+    let compiled = Arc::new(compile_sources(root, sources));
+
+    let mut bases: Vec<&Path> = compiled.includes.iter().map(|(base, _)| base.as_path()).collect();
+    if bases.is_empty() {
+        bases.push(Path::new(root));
+    }
+
+    let mut builder = WalkBuilder::new(bases[0]);
+    for base in &bases[1..] {
+        builder.add(base);
+    }
+
+    (builder, compiled)
+}
+
+fn is_allowed_walk_entry(entry: &ignore::DirEntry, compiled: &CompiledSources) -> bool {
+    if entry.file_type().unwrap().is_dir() {
+        if !entry
+            .file_name()
+            .to_str()
+            .map(|s| s != ".git")
+            .unwrap_or(false)
+        {
+            return false;
+        }
+
+        // Prune the whole subtree the moment it matches a negated pattern, instead of
+        // walking it and discarding every file underneath later.
+        return !matches_scoped(entry.path(), &compiled.excludes);
+    }
+
+    if matches_scoped(entry.path(), &compiled.excludes) {
+        return false;
+    }
+
+    if let Some((_, includes)) = compiled
+        .includes
+        .iter()
+        .find(|(base, _)| entry.path().starts_with(base))
+    {
+        if !includes.is_match(entry.path()) {
+            return false;
+        }
+    }
+
+    !compiled.ignore_config.is_ignored(entry.path())
 }
 
 pub fn resolve_content_paths(args: ContentPathInfo) -> Vec<String> {
     let root = args.base;
-    let paths: Vec<_> = WalkBuilder::new(&root)
-        .hidden(false)
-        .filter_entry(move |entry| {
-            // Skip known ignored folders
-            if entry.file_type().unwrap().is_dir() {
-                return entry
-                    .file_name()
-                    .to_str()
-                    .map(|s| s != ".git")
-                    .unwrap_or(false);
-            }
+    let (mut builder, compiled) = build_content_walker(&root, &args.sources);
 
-            is_allowed_content_path(entry.path())
-        })
+    let paths: Vec<_> = builder
+        .hidden(false)
+        .filter_entry(move |entry| is_allowed_walk_entry(entry, &compiled))
         .build()
         .filter_map(Result::ok)
         .filter(|e| e.path().is_file())
@@ -104,6 +277,70 @@ pub fn resolve_content_paths(args: ContentPathInfo) -> Vec<String> {
         .collect()
 }
 
+/// Walk, read and extract candidates in a single streaming pass instead of collecting every
+/// matching path into a `Vec`, then every file's bytes into a `Vec<Vec<u8>>`, then extracting
+/// from that. Reading and parsing happen right inside the parallel walk's own per-entry
+/// callback, so only the deduplicated candidate set ever needs to live in memory at once, and
+/// I/O for one file overlaps with parsing of another.
+#[tracing::instrument(skip(args))]
+pub fn scan(args: ContentPathInfo) -> Vec<String> {
+    let root = args.base.clone();
+    let (mut builder, compiled) = build_content_walker(&root, &args.sources);
+
+    let candidates: Mutex<BTreeSet<CompactString>> = Default::default();
+
+    builder.hidden(false).build_parallel().run(|| {
+        let compiled = Arc::clone(&compiled);
+        let candidates = &candidates;
+
+        Box::new(move |result| {
+            let Ok(entry) = result else {
+                return WalkState::Continue;
+            };
+
+            if !is_allowed_walk_entry(&entry, &compiled) {
+                // A rejected directory (`.git`, or one matching an exclude) is pruned, so
+                // nothing underneath it is visited.
+                return match entry.file_type() {
+                    Some(t) if t.is_dir() => WalkState::Skip,
+                    _ => WalkState::Continue,
+                };
+            }
+
+            if !entry.path().is_file() {
+                return WalkState::Continue;
+            }
+
+            let content = match read_file(entry.path()) {
+                Ok(content) => content,
+                Err(e) => {
+                    event!(tracing::Level::ERROR, "Failed to read file: {:?}", e);
+                    return WalkState::Continue;
+                }
+            };
+
+            let found = Extractor::unique(&content, Default::default());
+            let mut candidates = candidates.lock().unwrap();
+            candidates.extend(found.into_iter().map(|s| {
+                // SAFETY: Extractor guarantees these byte slices fall on valid UTF-8
+                // boundaries, so no re-validation is needed here.
+                CompactString::from(unsafe { std::str::from_utf8_unchecked(s) })
+            }));
+
+            WalkState::Continue
+        })
+    });
+
+    let mut result: Vec<String> = candidates
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    result.sort();
+    result
+}
+
 pub fn is_git_ignored_content_path(base: &Path, path: &Path) -> bool {
     !WalkBuilder::new(base)
         .hidden(false)
@@ -112,43 +349,55 @@ pub fn is_git_ignored_content_path(base: &Path, path: &Path) -> bool {
         .any(|e| e.path() == path)
 }
 
+/// Checks a path against the default (no project `.twignore`) ignore fixtures. The walk
+/// started from `resolve_content_paths`/`scan` instead consults a `CompiledSources`'
+/// project-aware `IgnoreConfig`, which falls back to these same defaults when no
+/// `.twignore` is present.
 pub fn is_allowed_content_path(path: &Path) -> bool {
-    let binary_extensions = include_str!("fixtures/binary-extensions.txt")
-        .trim()
-        .lines()
-        .collect::<Vec<_>>();
-    let ignored_extensions = include_str!("fixtures/ignored-extensions.txt")
-        .trim()
-        .lines()
-        .collect::<Vec<_>>();
-    let ignored_files = include_str!("fixtures/ignored-files.txt")
-        .trim()
-        .lines()
-        .collect::<Vec<_>>();
-
-    let path = PathBuf::from(path);
-
-    // Skip known ignored files
-    if path
-        .file_name()
-        .unwrap()
-        .to_str()
-        .map(|s| ignored_files.contains(&s))
-        .unwrap_or(false)
-    {
-        return false;
+    !IgnoreConfig::default().is_ignored(path)
+}
+
+/// Above this size, `read_file` memory-maps the file instead of reading it into a heap
+/// buffer, so the OS can page in only the regions the extractor actually touches instead of
+/// allocating and copying the whole file up-front.
+const MMAP_THRESHOLD_BYTES: u64 = 1024 * 1024;
+
+/// Either a heap-allocated buffer or a memory-mapped view over a file's contents.
+/// `Extractor::unique` only ever needs `&[u8]`, so callers don't need to care which one
+/// they got.
+enum FileBytes {
+    Owned(Vec<u8>),
+    Mapped(memmap2::Mmap),
+}
+
+impl std::ops::Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Owned(bytes) => bytes,
+            FileBytes::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+fn read_file(path: &Path) -> std::io::Result<FileBytes> {
+    let is_large = std::fs::metadata(path)?.len() > MMAP_THRESHOLD_BYTES;
+
+    if is_large {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: We only ever read from the mapping; the extractor treats the bytes as
+        // read-only input, same as a `Vec<u8>` returned by `std::fs::read`.
+        if let Ok(mmap) = unsafe { memmap2::Mmap::map(&file) } {
+            return Ok(FileBytes::Mapped(mmap));
+        }
     }
 
-    // Skip known ignored extensions
-    return path
-        .extension()
-        .map(|s| s.to_str().unwrap_or_default())
-        .map(|ext| !ignored_extensions.contains(&ext) && !binary_extensions.contains(&ext))
-        .unwrap_or(false);
+    std::fs::read(path).map(FileBytes::Owned)
 }
 
 #[tracing::instrument(skip(changed_content))]
-fn read_all_files(changed_content: Vec<ChangedContent>) -> Vec<Vec<u8>> {
+fn read_all_files(changed_content: Vec<ChangedContent>) -> Vec<FileBytes> {
     event!(
         tracing::Level::INFO,
         "Reading {:?} file(s)",
@@ -158,39 +407,153 @@ fn read_all_files(changed_content: Vec<ChangedContent>) -> Vec<Vec<u8>> {
     changed_content
         .into_par_iter()
         .map(|c| match (c.file, c.content) {
-            (Some(file), None) => match std::fs::read(file) {
+            (Some(file), None) => match read_file(&file) {
                 Ok(content) => content,
                 Err(e) => {
                     event!(tracing::Level::ERROR, "Failed to read file: {:?}", e);
-                    Default::default()
+                    FileBytes::Owned(Default::default())
                 }
             },
-            (None, Some(content)) => content.into_bytes(),
-            _ => Default::default(),
+            (None, Some(content)) => FileBytes::Owned(content.into_bytes()),
+            _ => FileBytes::Owned(Default::default()),
         })
         .collect()
 }
 
 #[tracing::instrument(skip(blobs))]
-fn parse_all_blobs(blobs: Vec<Vec<u8>>) -> Vec<String> {
+fn parse_all_blobs(blobs: Vec<FileBytes>) -> Vec<String> {
     let input: Vec<_> = blobs.iter().map(|blob| &blob[..]).collect();
     let input = &input[..];
 
     let mut result: Vec<String> = input
         .par_iter()
-        .map(|input| Extractor::unique(input, Default::default()))
+        .map(|input| {
+            Extractor::unique(input, Default::default())
+                .into_iter()
+                .map(|s| {
+                    // SAFETY: When we parsed the candidates, we already guaranteed that the
+                    // byte slices are valid, therefore we don't have to re-check here when we
+                    // want to convert it back to a string.
+                    CompactString::from(unsafe { std::str::from_utf8_unchecked(s) })
+                })
+                .collect::<BTreeSet<CompactString>>()
+        })
         .reduce(Default::default, |mut a, b| {
             a.extend(b);
             a
         })
         .into_iter()
-        .map(|s| {
-            // SAFETY: When we parsed the candidates, we already guaranteed that the byte slices
-            // are valid, therefore we don't have to re-check here when we want to convert it back
-            // to a string.
-            unsafe { String::from_utf8_unchecked(s.to_vec()) }
-        })
+        .map(|s| s.to_string())
         .collect();
     result.sort();
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tailwindcss-oxide-test-{name}-{}-{:?}",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn exclude_base_is_resolved_relative_to_root() {
+        // Root "." (the most common value) with a `base` given without a leading `./`,
+        // matching how `WalkBuilder::new(".")` actually prefixes its `DirEntry` paths.
+        let compiled = compile_sources(
+            ".",
+            &[Source {
+                base: "node_modules".into(),
+                pattern: "**".into(),
+                negated: true,
+            }],
+        );
+
+        assert!(matches_scoped(Path::new("./node_modules/dep.js"), &compiled.excludes));
+        assert!(!matches_scoped(Path::new("./src/app.js"), &compiled.excludes));
+    }
+
+    #[test]
+    fn exclude_base_is_resolved_relative_to_an_absolute_root() {
+        let compiled = compile_sources(
+            "/repo",
+            &[Source {
+                base: "node_modules".into(),
+                pattern: "**".into(),
+                negated: true,
+            }],
+        );
+
+        assert!(matches_scoped(Path::new("/repo/node_modules/dep.js"), &compiled.excludes));
+        assert!(!matches_scoped(Path::new("/repo/src/app.js"), &compiled.excludes));
+    }
+
+    #[test]
+    fn absolute_source_base_is_used_as_is() {
+        let compiled = compile_sources(
+            ".",
+            &[Source {
+                base: "/var/node_modules".into(),
+                pattern: "**".into(),
+                negated: true,
+            }],
+        );
+
+        assert!(matches_scoped(
+            Path::new("/var/node_modules/dep.js"),
+            &compiled.excludes
+        ));
+    }
+
+    #[test]
+    fn scan_skips_files_under_an_excluded_subtree() {
+        let root = unique_temp_dir("scan-exclude");
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(root.join("node_modules/dep.js"), "bg-red-500").unwrap();
+        fs::write(root.join("app.js"), "px-4 hover:bg-blue-500").unwrap();
+
+        let found = scan(ContentPathInfo {
+            base: root.to_string_lossy().into_owned(),
+            sources: vec![Source {
+                base: "node_modules".into(),
+                pattern: "**".into(),
+                negated: true,
+            }],
+        });
+
+        assert!(found.contains(&"px-4".to_string()));
+        assert!(!found.contains(&"bg-red-500".to_string()));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_content_paths_prunes_excluded_subtree() {
+        let root = unique_temp_dir("resolve-exclude");
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(root.join("node_modules/dep.js"), "").unwrap();
+        fs::write(root.join("app.js"), "").unwrap();
+
+        let patterns = resolve_content_paths(ContentPathInfo {
+            base: root.to_string_lossy().into_owned(),
+            sources: vec![Source {
+                base: "node_modules".into(),
+                pattern: "**".into(),
+                negated: true,
+            }],
+        });
+
+        assert!(!patterns.iter().any(|p| p.contains("node_modules")));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -15,6 +15,8 @@ pub struct ChangedContent {
 impl From<ChangedContent> for tailwindcss_core::ChangedContent {
   fn from(changed_content: ChangedContent) -> Self {
     tailwindcss_core::ChangedContent {
+      // Forwarded as-is, whether it points at a file or a directory — the core crate
+      // is responsible for expanding directories into their matching files.
       file: changed_content.file.map(PathBuf::from),
       content: changed_content.content,
       extension: changed_content.extension,
@@ -29,6 +31,49 @@ pub fn parse_candidate_strings_from_files(changed_content: Vec<ChangedContent>)
   )
 }
 
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct Source {
+  pub base: String,
+  pub pattern: String,
+  pub negated: bool,
+}
+
+impl From<Source> for tailwindcss_core::Source {
+  fn from(source: Source) -> Self {
+    tailwindcss_core::Source {
+      base: source.base,
+      pattern: source.pattern,
+      negated: source.negated,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+#[napi(object)]
+pub struct ContentPathInfo {
+  pub base: String,
+  pub sources: Vec<Source>,
+}
+
+impl From<ContentPathInfo> for tailwindcss_core::ContentPathInfo {
+  fn from(info: ContentPathInfo) -> Self {
+    tailwindcss_core::ContentPathInfo {
+      base: info.base,
+      sources: info.sources.into_iter().map(Into::into).collect(),
+    }
+  }
+}
+
+/// Walks `args.base` (scoped by `args.sources`), reading and extracting candidates from
+/// every matching file in one fused pass. This is the production entry point for the
+/// single-streaming-pipeline path: unlike `parse_candidate_strings_from_files`, which takes
+/// an already-resolved list of files/content blobs, `scan` does the directory walk itself.
+#[napi]
+pub fn scan(args: ContentPathInfo) -> Vec<String> {
+  tailwindcss_core::scan(args.into())
+}
+
 #[derive(Debug)]
 #[napi]
 pub enum IO {